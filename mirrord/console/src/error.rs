@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+pub type Result<T, E = ConsoleError> = std::result::Result<T, E>;
+
+/// Errors that can occur while the console logger talks to the console app.
+#[derive(Debug, Error)]
+pub enum ConsoleError {
+    #[error("WebSocket error: {0}")]
+    WebSocketError(#[from] async_tungstenite::tungstenite::Error),
+
+    #[error("failed to serialize console message: {0}")]
+    SerializationError(#[from] serde_json::Error),
+
+    #[error("failed to set the global logger: {0}")]
+    SetLoggerError(#[from] log::SetLoggerError),
+
+    #[error("TLS error: {0}")]
+    TlsError(#[from] native_tls::Error),
+
+    #[error("TLS config was given but the address explicitly uses ws://")]
+    TlsSchemeMismatch,
+}
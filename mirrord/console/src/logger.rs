@@ -1,35 +1,155 @@
 use std::{
-    io::{Read, Write},
-    sync::mpsc::{sync_channel, Receiver, SyncSender},
-    thread,
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
+use arc_swap::ArcSwap;
+use async_tungstenite::tungstenite::{protocol::Message, Error as WsError};
+use futures::{channel::mpsc, stream::Fuse, FutureExt, Sink, SinkExt, StreamExt};
 use log::{LevelFilter, Metadata};
-use tungstenite::{connect, protocol::Message, WebSocket};
+use rand::Rng;
 
 use crate::{
     error::{ConsoleError, Result},
     protocol,
 };
 
-/// Console logger that sends log messages to the console app.
+#[cfg(feature = "tokio-runtime")]
+type Connection = async_tungstenite::WebSocketStream<async_tungstenite::tokio::ConnectStream>;
+#[cfg(feature = "async-std-runtime")]
+type Connection =
+    async_tungstenite::WebSocketStream<async_tungstenite::async_std::ConnectStream>;
+
+/// Targets the logger forwards, consulted on every `log()` call. Swapped out
+/// live when the console app sends a `SetTargets` command.
+type TargetFilter = Arc<ArcSwap<Vec<String>>>;
+
+fn default_targets() -> TargetFilter {
+    Arc::new(ArcSwap::from_pointee(vec!["mirrord".to_string()]))
+}
+
+/// TLS configuration for connecting to a console app over `wss://`, for
+/// deployments where the agent and the console app are on different hosts.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    /// PEM-encoded root certificate to trust, for self-signed console
+    /// deployments behind a cluster ingress.
+    pub root_cert: Option<Vec<u8>>,
+    /// Skip certificate validation entirely. Only for local debugging.
+    pub danger_accept_invalid_certs: bool,
+}
+
+fn build_tls_connector(tls: &TlsConfig) -> Result<native_tls::TlsConnector> {
+    let mut builder = native_tls::TlsConnector::builder();
+    if let Some(root_cert) = &tls.root_cert {
+        builder.add_root_certificate(native_tls::Certificate::from_pem(root_cert)?);
+    }
+    builder.danger_accept_invalid_certs(tls.danger_accept_invalid_certs);
+    Ok(builder.build()?)
+}
+
+/// Builds the endpoint to dial. `address` may be a bare `host:port`, in
+/// which case the scheme is picked from whether `tls` is set, or a full URL
+/// with an explicit `ws://`/`wss://` scheme, in which case it's used as-is
+/// unless it conflicts with `tls` (an explicit `ws://` with a TLS config is
+/// almost certainly a mistake, so we reject it rather than silently
+/// dropping the TLS config).
+fn endpoint(address: &str, tls: Option<&TlsConfig>) -> Result<String> {
+    if let Some((scheme, _)) = address.split_once("://") {
+        if scheme == "ws" && tls.is_some() {
+            return Err(ConsoleError::TlsSchemeMismatch);
+        }
+        return Ok(address.to_string());
+    }
+
+    let scheme = if tls.is_some() { "wss" } else { "ws" };
+    Ok(format!("{scheme}://{address}/ws"))
+}
+
+/// Number of most-recent records kept around so a reconnect can replay the
+/// backlog the console app missed while we were disconnected.
+const BACKLOG_CAPACITY: usize = 256;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(200);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Coalesces records drained from the channel into a single
+/// [`protocol::RecordBatch`] frame instead of one frame per record.
+#[derive(Debug, Clone)]
+pub struct BatchConfig {
+    /// Flush immediately once this many records have accumulated.
+    pub max_size: usize,
+    /// Flush whatever has accumulated once this long has passed since the
+    /// first record in the batch arrived.
+    pub window: Duration,
+}
+
+/// Knobs for [`init_logger_with_config`].
+#[derive(Debug, Clone)]
+pub struct LoggerConfig {
+    /// TLS configuration for a `wss://` console, or `None` for plain `ws://`
+    /// (an address that carries its own `wss://` scheme is still dialed as
+    /// given).
+    pub tls: Option<TlsConfig>,
+    /// How long the connection may sit idle before a keepalive Ping is sent.
+    pub keepalive_interval: Duration,
+    /// How long to wait for the matching Pong before the connection is
+    /// considered dead and a reconnect is triggered.
+    pub pong_timeout: Duration,
+    /// Batch outgoing records instead of writing one frame per record.
+    /// Disabled (one frame per record) by default.
+    pub batch: Option<BatchConfig>,
+}
+
+impl Default for LoggerConfig {
+    fn default() -> Self {
+        Self {
+            tls: None,
+            keepalive_interval: Duration::from_secs(30),
+            pong_timeout: Duration::from_secs(10),
+            batch: None,
+        }
+    }
+}
+
+/// Console logger that forwards log messages to the console app over a
+/// non-blocking channel, sharing the host application's async runtime
+/// instead of owning a dedicated OS thread.
 pub struct ConsoleLogger {
-    sender: SyncSender<protocol::Record>,
+    sender: mpsc::Sender<protocol::Record>,
+    targets: TargetFilter,
+    /// Whether `logger_task` currently has a live connection. Lets `log()`
+    /// skip noisy error logging while we know a reconnect is in progress,
+    /// rather than complaining on every dropped message into the channel.
+    connected: Arc<AtomicBool>,
 }
 
 impl log::Log for ConsoleLogger {
-    /// Returns true if the log is generated by mirrord code.
+    /// Returns true if the log's target matches one of the configured
+    /// targets. Defaults to just `mirrord`, but the console app can widen or
+    /// narrow this set at runtime via a `SetTargets` command.
+    ///
     /// We can have this more fine-grained and also inclusive but
     /// be aware that you might get into a recursive scenario if you let
     /// websocket module logs slide in.
     fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.target().contains("mirrord")
+        self.targets
+            .load()
+            .iter()
+            .any(|target| metadata.target().contains(target.as_str()))
     }
 
     /// Serialize the logs into our protocol then send it over the wire.
+    ///
+    /// Uses `try_send` so a full channel or a not-yet-drained backlog never
+    /// blocks the caller.
     fn log(&self, record: &log::Record) {
         if self.enabled(record.metadata()) {
-            match self.sender.send(protocol::Record {
+            if let Err(e) = self.sender.clone().try_send(protocol::Record {
                 metadata: protocol::Metadata {
                     level: record.level(),
                     target: record.target().to_string(),
@@ -39,8 +159,7 @@ impl log::Log for ConsoleLogger {
                 file: record.file().map(|s| s.to_string()),
                 line: record.line(),
             }) {
-                Ok(_) => {}
-                Err(e) => {
+                if self.connected.load(Ordering::Relaxed) {
                     eprintln!("Error sending log message: {e:?}");
                 }
             }
@@ -51,7 +170,10 @@ impl log::Log for ConsoleLogger {
 }
 
 /// Send hello message, containing information about the connected process.
-fn send_hello<S: Read + Write>(client: &mut WebSocket<S>) -> Result<()> {
+async fn send_hello<S>(client: &mut S) -> Result<()>
+where
+    S: Sink<Message, Error = WsError> + Unpin,
+{
     let hello = protocol::Hello {
         process_info: protocol::ProcessInfo {
             args: std::env::args().collect(),
@@ -59,38 +181,569 @@ fn send_hello<S: Read + Write>(client: &mut WebSocket<S>) -> Result<()> {
             cwd: std::env::current_dir()
                 .map(|p| p.to_str().map(String::from))
                 .unwrap_or(None),
-            id: std::process::id().into(),
+            id: std::process::id(),
         },
     };
-    let msg = Message::binary(serde_json::to_vec(&hello).unwrap());
-    client.write_message(msg)?;
+    let msg = Message::binary(serde_json::to_vec(&hello)?);
+    client.send(msg).await?;
     Ok(())
 }
 
-/// Background task that does the communication
-/// with the console app.
-fn logger_task<S: Read + Write>(mut client: WebSocket<S>, rx: Receiver<protocol::Record>) {
-    while let Ok(msg) = rx.recv() {
-        let msg = Message::binary(serde_json::to_vec(&msg).unwrap());
-        if let Err(err) = client.write_message(msg) {
-            eprintln!("Error sending log message: {err:?}");
-            break;
+/// Writes a single record to the console app.
+async fn write_message<S>(client: &mut S, record: &protocol::Record) -> Result<()>
+where
+    S: Sink<Message, Error = WsError> + Unpin,
+{
+    let msg = Message::binary(serde_json::to_vec(record)?);
+    client.send(msg).await?;
+    Ok(())
+}
+
+/// Writes a batch of records as a single frame, preserving their order.
+async fn write_batch<S>(client: &mut S, records: &[protocol::Record]) -> Result<()>
+where
+    S: Sink<Message, Error = WsError> + Unpin,
+{
+    let batch = protocol::RecordBatch {
+        records: records.to_vec(),
+    };
+    let msg = Message::binary(serde_json::to_vec(&batch)?);
+    client.send(msg).await?;
+    Ok(())
+}
+
+/// Applies a command sent back by the console app.
+fn apply_command(command: protocol::Command, targets: &TargetFilter) {
+    match command {
+        protocol::Command::SetLevel(level) => log::set_max_level(level),
+        protocol::Command::SetTargets(new_targets) => targets.store(Arc::new(new_targets)),
+    }
+}
+
+/// Handles one inbound message from the console app, if it decodes as a
+/// known command.
+fn handle_incoming(msg: Message, targets: &TargetFilter) {
+    let Message::Binary(bytes) = msg else {
+        return;
+    };
+    match serde_json::from_slice::<protocol::Command>(&bytes) {
+        Ok(command) => apply_command(command, targets),
+        Err(err) => eprintln!("Error decoding console command: {err:?}"),
+    }
+}
+
+/// Doubles `current`, capped at [`MAX_BACKOFF`], then adds up to 25% jitter
+/// so a console app restart doesn't get hammered by every reconnecting
+/// process on the same tick.
+fn next_backoff(current: Duration) -> Duration {
+    let doubled = (current * 2).min(MAX_BACKOFF);
+    let jitter_ms = rand::thread_rng().gen_range(0..=(doubled.as_millis() as u64 / 4).max(1));
+    (doubled + Duration::from_millis(jitter_ms)).min(MAX_BACKOFF)
+}
+
+/// Pushes onto the backlog, dropping the oldest record first if it's full.
+fn push_backlog(backlog: &mut VecDeque<protocol::Record>, record: protocol::Record) {
+    if backlog.len() >= BACKLOG_CAPACITY {
+        backlog.pop_front();
+    }
+    backlog.push_back(record);
+}
+
+/// How long until the next keepalive action is due: the Pong deadline if
+/// `ping_sent_at` is set (a Ping is in flight), otherwise the
+/// idle-before-Ping interval counted from `last_activity`.
+///
+/// `ping_sent_at` and `last_activity` are tracked separately on purpose:
+/// `last_activity` also advances on every successful outgoing write, and a
+/// busy connection must not let that push back the deadline for a Pong that
+/// a dead read side will never deliver.
+fn keepalive_delay(
+    ping_sent_at: Option<Instant>,
+    last_activity: Instant,
+    config: &LoggerConfig,
+) -> Duration {
+    match ping_sent_at {
+        Some(sent) => config.pong_timeout.saturating_sub(sent.elapsed()),
+        None => config
+            .keepalive_interval
+            .saturating_sub(last_activity.elapsed()),
+    }
+}
+
+/// Whether the keepalive deadline computed by [`keepalive_delay`] has
+/// actually elapsed.
+fn keepalive_due(ping_sent_at: Option<Instant>, last_activity: Instant, config: &LoggerConfig) -> bool {
+    match ping_sent_at {
+        Some(sent) => sent.elapsed() >= config.pong_timeout,
+        None => last_activity.elapsed() >= config.keepalive_interval,
+    }
+}
+
+/// How long until the current batch window closes, or `None` if batching is
+/// disabled or no batch is in progress.
+fn batch_delay(batch: Option<&BatchConfig>, batch_started: Option<Instant>) -> Option<Duration> {
+    batch
+        .zip(batch_started)
+        .map(|(batch, started)| batch.window.saturating_sub(started.elapsed()))
+}
+
+/// Whether the batch window computed by [`batch_delay`] has actually
+/// elapsed.
+fn batch_due(batch: Option<&BatchConfig>, batch_started: Option<Instant>) -> bool {
+    batch
+        .zip(batch_started)
+        .is_some_and(|(batch, started)| started.elapsed() >= batch.window)
+}
+
+/// Waits out a backoff delay, buffering any records that arrive from the
+/// host application in the meantime instead of letting them sit unread in
+/// the channel.
+async fn wait_backoff(
+    delay: Duration,
+    rx: &mut Fuse<mpsc::Receiver<protocol::Record>>,
+    backlog: &mut VecDeque<protocol::Record>,
+) {
+    let mut delay = Box::pin(sleep(delay).fuse());
+    loop {
+        futures::select! {
+            () = delay => break,
+            record = rx.next() => {
+                match record {
+                    Some(record) => push_backlog(backlog, record),
+                    None => break,
+                }
+            }
+        }
+    }
+}
+
+/// Re-dials the console app with exponential backoff, re-sending `Hello` on
+/// each successful connection, until one sticks.
+///
+/// The connection attempt itself can take a while (DNS, TLS handshake), so
+/// `rx` is drained into `backlog` concurrently with it instead of only
+/// between attempts, otherwise records sent by the host application while
+/// a connect attempt is in flight would just sit unread in the channel.
+async fn reconnect(
+    address: &str,
+    tls: Option<&TlsConfig>,
+    rx: &mut Fuse<mpsc::Receiver<protocol::Record>>,
+    backlog: &mut VecDeque<protocol::Record>,
+) -> Connection {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        let mut attempt = Box::pin(
+            async {
+                let mut client = connect(address, tls).await.map_err(|err| {
+                    eprintln!("Error reconnecting to console: {err:?}");
+                })?;
+                send_hello(&mut client).await.map_err(|err| {
+                    eprintln!("Error sending hello after reconnect: {err:?}");
+                })?;
+                Ok::<_, ()>(client)
+            }
+            .fuse(),
+        );
+
+        let outcome = loop {
+            futures::select! {
+                result = attempt => break result,
+                record = rx.next() => {
+                    if let Some(record) = record {
+                        push_backlog(backlog, record);
+                    }
+                }
+            }
+        };
+
+        if let Ok(client) = outcome {
+            return client;
+        }
+
+        wait_backoff(backoff, rx, backlog).await;
+        backoff = next_backoff(backoff);
+    }
+}
+
+/// Background task that does the communication with the console app,
+/// `.await`ing writes on the host application's own runtime rather than
+/// blocking a dedicated thread.
+///
+/// Makes the initial connection itself, through the same backoff-aware
+/// [`reconnect`] used for every later reconnect, instead of the caller
+/// connecting up front: `init_logger` is a plain sync fn that only spawns
+/// this task, so it never waits on the connection itself.
+///
+/// Multiplexes between outgoing records, commands the console app sends
+/// back, and a keepalive timer, so the logger's behavior can be changed live
+/// and a silently-dropped connection is noticed instead of just piling up
+/// unsent records. If the connection drops, reconnects with backoff and
+/// replays the most recent buffered records first. When `config.batch` is
+/// set, outgoing records are coalesced into `RecordBatch` frames instead of
+/// one frame per record.
+async fn logger_task(
+    rx: mpsc::Receiver<protocol::Record>,
+    targets: TargetFilter,
+    address: String,
+    config: LoggerConfig,
+    connected: Arc<AtomicBool>,
+) {
+    let mut rx = rx.fuse();
+    let mut backlog: VecDeque<protocol::Record> = VecDeque::with_capacity(BACKLOG_CAPACITY);
+    let mut client = reconnect(&address, config.tls.as_ref(), &mut rx, &mut backlog).await;
+    connected.store(true, Ordering::Relaxed);
+
+    loop {
+        let mut client_ref = (&mut client).fuse();
+
+        while let Some(record) = backlog.pop_front() {
+            if let Err(err) = write_message(&mut client_ref, &record).await {
+                eprintln!("Error replaying buffered log message: {err:?}");
+                backlog.push_front(record);
+                break;
+            }
         }
+
+        let mut last_activity = Instant::now();
+        let mut ping_sent_at: Option<Instant> = None;
+        let mut disconnected = false;
+        let mut pending: Vec<protocol::Record> = Vec::new();
+        let mut batch_started: Option<Instant> = None;
+
+        while !disconnected {
+            let timer_delay = match batch_delay(config.batch.as_ref(), batch_started) {
+                Some(batch_delay) => {
+                    batch_delay.min(keepalive_delay(ping_sent_at, last_activity, &config))
+                }
+                None => keepalive_delay(ping_sent_at, last_activity, &config),
+            };
+            let mut timer = Box::pin(sleep(timer_delay).fuse());
+
+            futures::select! {
+                record = rx.next() => {
+                    let Some(record) = record else { return };
+                    match &config.batch {
+                        Some(batch) => {
+                            if pending.is_empty() {
+                                batch_started = Some(Instant::now());
+                            }
+                            pending.push(record);
+                            if pending.len() >= batch.max_size {
+                                let to_send = std::mem::take(&mut pending);
+                                batch_started = None;
+                                if let Err(err) = write_batch(&mut client_ref, &to_send).await {
+                                    eprintln!("Error sending log batch: {err:?}");
+                                    to_send.into_iter().for_each(|r| push_backlog(&mut backlog, r));
+                                    disconnected = true;
+                                } else {
+                                    last_activity = Instant::now();
+                                }
+                            }
+                        }
+                        None => {
+                            if let Err(err) = write_message(&mut client_ref, &record).await {
+                                eprintln!("Error sending log message: {err:?}");
+                                push_backlog(&mut backlog, record);
+                                disconnected = true;
+                            } else {
+                                last_activity = Instant::now();
+                            }
+                        }
+                    }
+                }
+                incoming = client_ref.next() => {
+                    match incoming {
+                        Some(Ok(Message::Pong(_))) => {
+                            ping_sent_at = None;
+                            last_activity = Instant::now();
+                        }
+                        Some(Ok(Message::Ping(payload))) => {
+                            if let Err(err) = client_ref.send(Message::Pong(payload)).await {
+                                eprintln!("Error replying to ping: {err:?}");
+                                disconnected = true;
+                            }
+                        }
+                        Some(Ok(Message::Close(_))) => {
+                            disconnected = true;
+                        }
+                        Some(Ok(msg)) => handle_incoming(msg, &targets),
+                        Some(Err(err)) => {
+                            eprintln!("Error reading from console: {err:?}");
+                            disconnected = true;
+                        }
+                        None => disconnected = true,
+                    }
+                }
+                () = timer => {
+                    if batch_due(config.batch.as_ref(), batch_started) && !pending.is_empty() {
+                        let to_send = std::mem::take(&mut pending);
+                        batch_started = None;
+                        if let Err(err) = write_batch(&mut client_ref, &to_send).await {
+                            eprintln!("Error sending log batch: {err:?}");
+                            to_send.into_iter().for_each(|r| push_backlog(&mut backlog, r));
+                            disconnected = true;
+                        } else {
+                            last_activity = Instant::now();
+                        }
+                    }
+
+                    if !disconnected && keepalive_due(ping_sent_at, last_activity, &config) {
+                        if ping_sent_at.is_some() {
+                            eprintln!("Missed keepalive pong from console, reconnecting");
+                            disconnected = true;
+                        } else if let Err(err) = client_ref.send(Message::Ping(Vec::new())).await {
+                            eprintln!("Error sending keepalive ping: {err:?}");
+                            disconnected = true;
+                        } else {
+                            ping_sent_at = Some(Instant::now());
+                        }
+                    }
+                }
+            }
+        }
+
+        // `disconnected` can be set from several arms above (a failed batch
+        // write already flushes `pending` itself, but a Pong timeout, a
+        // failed Ping reply, a Close frame, a read error or the stream
+        // ending all set it too) without touching `pending`. Flush whatever
+        // is left here so it isn't silently dropped on the next iteration.
+        std::mem::take(&mut pending)
+            .into_iter()
+            .for_each(|record| push_backlog(&mut backlog, record));
+
+        connected.store(false, Ordering::Relaxed);
+        client = reconnect(&address, config.tls.as_ref(), &mut rx, &mut backlog).await;
+        connected.store(true, Ordering::Relaxed);
     }
 }
 
-/// Initializes the logger
-/// Connects to the console, and sets the global logger to use it.
+#[cfg(feature = "tokio-runtime")]
+async fn connect(address: &str, tls: Option<&TlsConfig>) -> Result<Connection> {
+    let url = endpoint(address, tls)?;
+    let connector = tls
+        .map(build_tls_connector)
+        .transpose()?
+        .map(async_tungstenite::tokio::Connector::NativeTls);
+    let (stream, _) =
+        async_tungstenite::tokio::connect_async_tls_with_config(url, None, false, connector)
+            .await?;
+    Ok(stream)
+}
+
+#[cfg(feature = "async-std-runtime")]
+async fn connect(address: &str, tls: Option<&TlsConfig>) -> Result<Connection> {
+    let url = endpoint(address, tls)?;
+    let connector = tls.map(build_tls_connector).transpose()?;
+    let (stream, _) =
+        async_tungstenite::async_std::connect_async_tls_with_config(url, None, false, connector)
+            .await?;
+    Ok(stream)
+}
+
+#[cfg(feature = "tokio-runtime")]
+fn spawn(task: impl std::future::Future<Output = ()> + Send + 'static) {
+    tokio::spawn(task);
+}
+
+#[cfg(feature = "async-std-runtime")]
+fn spawn(task: impl std::future::Future<Output = ()> + Send + 'static) {
+    async_std::task::spawn(task);
+}
+
+#[cfg(feature = "tokio-runtime")]
+async fn sleep(duration: Duration) {
+    tokio::time::sleep(duration).await;
+}
+
+#[cfg(feature = "async-std-runtime")]
+async fn sleep(duration: Duration) {
+    async_std::task::sleep(duration).await;
+}
+
+/// Initializes the logger.
+/// Sets the global logger to use it, and connects to the console in the
+/// background once it's spawned onto the host application's runtime.
 pub fn init_logger(address: &str) -> Result<()> {
-    let (tx, rx) = sync_channel(10000);
-    let (mut client, _) =
-        connect(format!("ws://{address}/ws")).map_err(ConsoleError::ConnectError)?;
-
-    send_hello(&mut client)?;
-    thread::spawn(move || {
-        logger_task(client, rx);
-    });
-    let logger = ConsoleLogger { sender: tx };
+    init_logger_with_config(address, LoggerConfig::default())
+}
+
+/// Like [`init_logger`], but connects over `wss://` using the given TLS
+/// configuration when `tls` is `Some`. `address` may carry its own
+/// `ws://`/`wss://` scheme; an explicit `ws://` together with `Some(tls)`
+/// is rejected rather than silently ignoring the TLS config.
+pub fn init_logger_with_tls(address: &str, tls: Option<TlsConfig>) -> Result<()> {
+    init_logger_with_config(
+        address,
+        LoggerConfig {
+            tls,
+            ..Default::default()
+        },
+    )
+}
+
+/// Like [`init_logger`], with full control over TLS, keepalive and batching
+/// behavior.
+///
+/// Returns as soon as the logger is registered; the actual connection is
+/// made in the background (see [`logger_task`]), so this never blocks
+/// waiting on it. With the `tokio-runtime` feature the task is spawned with
+/// `tokio::spawn`, so this must be called from within a Tokio runtime (it
+/// panics otherwise); with `async-std-runtime` the task runs on async-std's
+/// global executor.
+pub fn init_logger_with_config(address: &str, config: LoggerConfig) -> Result<()> {
+    let (tx, rx) = mpsc::channel(10000);
+    let targets = default_targets();
+    let connected = Arc::new(AtomicBool::new(false));
+
+    spawn(logger_task(
+        rx,
+        targets.clone(),
+        address.to_string(),
+        config,
+        connected.clone(),
+    ));
+
+    let logger = ConsoleLogger {
+        sender: tx,
+        targets,
+        connected,
+    };
     log::set_boxed_logger(Box::new(logger)).map(|()| log::set_max_level(LevelFilter::Trace))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> protocol::Record {
+        protocol::Record {
+            metadata: protocol::Metadata {
+                level: log::Level::Info,
+                target: "mirrord".to_string(),
+            },
+            message: "hello".to_string(),
+            module_path: None,
+            file: None,
+            line: None,
+        }
+    }
+
+    #[test]
+    fn next_backoff_doubles_up_to_the_cap() {
+        let mut backoff = INITIAL_BACKOFF;
+        for _ in 0..32 {
+            let next = next_backoff(backoff);
+            assert!(next >= backoff.min(MAX_BACKOFF));
+            assert!(next <= MAX_BACKOFF);
+            backoff = next;
+        }
+        assert_eq!(backoff, MAX_BACKOFF);
+    }
+
+    #[test]
+    fn push_backlog_drops_oldest_once_full() {
+        let mut backlog = VecDeque::new();
+        for _ in 0..BACKLOG_CAPACITY {
+            push_backlog(&mut backlog, record());
+        }
+        assert_eq!(backlog.len(), BACKLOG_CAPACITY);
+
+        let overflow = protocol::Record {
+            message: "overflow".to_string(),
+            ..record()
+        };
+        push_backlog(&mut backlog, overflow);
+
+        assert_eq!(backlog.len(), BACKLOG_CAPACITY);
+        assert_eq!(backlog.back().unwrap().message, "overflow");
+    }
+
+    #[test]
+    fn keepalive_due_waits_out_the_idle_interval() {
+        let config = LoggerConfig {
+            keepalive_interval: Duration::from_millis(10),
+            pong_timeout: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let last_activity = Instant::now();
+
+        assert!(!keepalive_due(None, last_activity, &config));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(keepalive_due(None, last_activity, &config));
+    }
+
+    #[test]
+    fn keepalive_due_tracks_the_pong_deadline_from_ping_sent_at_not_last_activity() {
+        let config = LoggerConfig {
+            keepalive_interval: Duration::from_millis(10),
+            pong_timeout: Duration::from_millis(10),
+            ..Default::default()
+        };
+        let ping_sent_at = Instant::now();
+        std::thread::sleep(Duration::from_millis(20));
+        // A write that happened long after the Ping was sent keeps bumping
+        // `last_activity`, but must not push back the Pong deadline, which
+        // is tracked from `ping_sent_at` alone.
+        let last_activity = Instant::now();
+
+        assert!(keepalive_due(Some(ping_sent_at), last_activity, &config));
+    }
+
+    #[test]
+    fn batch_due_is_false_without_a_started_batch() {
+        let batch = BatchConfig {
+            max_size: 10,
+            window: Duration::from_millis(10),
+        };
+        assert!(!batch_due(Some(&batch), None));
+        assert!(!batch_due(None, Some(Instant::now())));
+    }
+
+    #[test]
+    fn batch_due_fires_once_the_window_elapses() {
+        let batch = BatchConfig {
+            max_size: 10,
+            window: Duration::from_millis(10),
+        };
+        let started = Instant::now();
+
+        assert!(!batch_due(Some(&batch), Some(started)));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(batch_due(Some(&batch), Some(started)));
+    }
+
+    #[test]
+    fn endpoint_bare_address_without_tls_is_plain_ws() {
+        assert_eq!(
+            endpoint("localhost:1234", None).unwrap(),
+            "ws://localhost:1234/ws"
+        );
+    }
+
+    #[test]
+    fn endpoint_bare_address_with_tls_is_wss() {
+        let tls = TlsConfig::default();
+        assert_eq!(
+            endpoint("localhost:1234", Some(&tls)).unwrap(),
+            "wss://localhost:1234/ws"
+        );
+    }
+
+    #[test]
+    fn endpoint_explicit_ws_scheme_with_tls_is_rejected() {
+        let tls = TlsConfig::default();
+        assert!(matches!(
+            endpoint("ws://localhost:1234", Some(&tls)),
+            Err(ConsoleError::TlsSchemeMismatch)
+        ));
+    }
+
+    #[test]
+    fn endpoint_explicit_wss_scheme_is_passed_through_unchanged() {
+        assert_eq!(
+            endpoint("wss://localhost:1234/custom", None).unwrap(),
+            "wss://localhost:1234/custom"
+        );
+    }
+}
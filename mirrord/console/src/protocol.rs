@@ -0,0 +1,51 @@
+use log::{Level, LevelFilter};
+use serde::{Deserialize, Serialize};
+
+/// Mirrors [`log::Metadata`], which isn't itself serializable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Metadata {
+    pub level: Level,
+    pub target: String,
+}
+
+/// A single log record sent to the console app.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub metadata: Metadata,
+    pub message: String,
+    pub module_path: Option<String>,
+    pub file: Option<String>,
+    pub line: Option<u32>,
+}
+
+/// Information about the process mirrord is running in, sent once on connect.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub args: Vec<String>,
+    pub env: Vec<String>,
+    pub cwd: Option<String>,
+    pub id: u32,
+}
+
+/// First message sent by the logger once connected to the console app.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Hello {
+    pub process_info: ProcessInfo,
+}
+
+/// Command sent by the console app back to the logger, to change its
+/// behavior at runtime without restarting the mirrored process.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum Command {
+    /// Change the global log level filter.
+    SetLevel(LevelFilter),
+    /// Replace the set of targets the logger forwards.
+    SetTargets(Vec<String>),
+}
+
+/// Multiple records coalesced into a single frame, to cut per-message
+/// overhead under heavy logging. Ordering of `records` is preserved.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RecordBatch {
+    pub records: Vec<Record>,
+}